@@ -0,0 +1,133 @@
+//! Autonomous helmsman for opponent boats: sails a queue of waypoints,
+//! including beating upwind when the direct bearing falls inside the no-go
+//! zone.
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier2d::prelude::Velocity as RapierVelocity;
+
+use crate::{Constants, Object, Sail, TurnRadius, Wind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum Tack {
+    Port,
+    Starboard,
+}
+
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct Waypoint(pub Vec2);
+
+/// Autonomous helm: steers `TurnRadius` and trims `Sail` to work through a
+/// queue of waypoints, beating upwind by tacking when necessary.
+#[derive(Component, Reflect)]
+pub struct AiHelm {
+    pub waypoints: Vec<Waypoint>,
+    pub waypoint_radius: f32,
+    pub cross_track_threshold: f32,
+    tack: Tack,
+    beating: bool,
+    tack_start: Vec2,
+}
+
+impl AiHelm {
+    pub fn new(waypoints: Vec<Waypoint>) -> Self {
+        Self {
+            waypoints,
+            waypoint_radius: 30.,
+            cross_track_threshold: 150.,
+            tack: Tack::Port,
+            beating: false,
+            tack_start: Vec2::ZERO,
+        }
+    }
+}
+
+pub fn sys_ai_helm(
+    wind: Res<Wind>,
+    constants: Res<Constants>,
+    mut q_boat: Query<(&mut AiHelm, &Transform, &RapierVelocity, &mut TurnRadius, &Children), With<Object>>,
+    mut q_sail: Query<&mut Transform, (With<Sail>, Without<AiHelm>)>,
+) {
+    let no_go_half_angle = constants.ai_no_go_half_angle_degrees.to_radians();
+
+    for (mut helm, boat_xf, boat_velocity, mut turn_radius, children) in &mut q_boat {
+        let boat_pos = boat_xf.translation.xy();
+        let boat_dir = boat_xf.up().xy();
+        let boat_angle = Vec2::Y.angle_to(boat_dir);
+
+        let Some(&Waypoint(target)) = helm.waypoints.first() else {
+            turn_radius.0 = f32::INFINITY;
+            continue;
+        };
+
+        if boat_pos.distance(target) <= helm.waypoint_radius {
+            helm.waypoints.remove(0);
+            helm.beating = false;
+            continue;
+        }
+
+        let bearing_to_target = (target - boat_pos).normalize();
+        let upwind_dir = -wind.0.normalize_or_zero();
+        let angle_from_upwind = upwind_dir.angle_to(bearing_to_target).abs();
+
+        let desired_heading = if angle_from_upwind < no_go_half_angle {
+            let port_heading = upwind_dir.rotate(Vec2::from_angle(-no_go_half_angle));
+            let starboard_heading = upwind_dir.rotate(Vec2::from_angle(no_go_half_angle));
+
+            if !helm.beating {
+                helm.beating = true;
+                helm.tack_start = boat_pos;
+                helm.tack = if port_heading.dot(bearing_to_target) >= starboard_heading.dot(bearing_to_target) {
+                    Tack::Port
+                } else {
+                    Tack::Starboard
+                };
+            } else {
+                let rhumb_dir = bearing_to_target;
+                let perp_dir = Vec2::new(-rhumb_dir.y, rhumb_dir.x);
+                let cross_track = (boat_pos - helm.tack_start).dot(perp_dir);
+
+                let (current_heading, opposite_heading) = match helm.tack {
+                    Tack::Port => (port_heading, starboard_heading),
+                    Tack::Starboard => (starboard_heading, port_heading),
+                };
+                let opposite_now_closer = opposite_heading.dot(bearing_to_target) > current_heading.dot(bearing_to_target);
+
+                if cross_track.abs() > helm.cross_track_threshold || opposite_now_closer {
+                    helm.tack = match helm.tack {
+                        Tack::Port => Tack::Starboard,
+                        Tack::Starboard => Tack::Port,
+                    };
+                    helm.tack_start = boat_pos;
+                }
+            }
+
+            match helm.tack {
+                Tack::Port => port_heading,
+                Tack::Starboard => starboard_heading,
+            }
+        } else {
+            helm.beating = false;
+            bearing_to_target
+        };
+
+        let heading_error = boat_dir.angle_to(desired_heading);
+        turn_radius.0 = if heading_error.abs() < 0.02 {
+            f32::INFINITY
+        } else if heading_error > 0. {
+            constants.boat_turn_radius
+        } else {
+            -constants.boat_turn_radius
+        };
+
+        // Auto-trim: bisect the boat's heading and the apparent wind.
+        let apparent_wind = wind.0 - boat_velocity.linvel;
+        let trim_dir = (boat_dir - apparent_wind.normalize_or_zero()).normalize_or(boat_dir);
+        let trim_angle = Vec2::Y.angle_to(trim_dir) - boat_angle;
+
+        for child in children.iter() {
+            if let Ok(mut sail_xf) = q_sail.get_mut(child) {
+                sail_xf.rotation = Quat::from_rotation_z(trim_angle);
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Sprite-atlas rendering: resolves an `AtlasSprite` name against a packed
+//! texture atlas (image + per-sprite metadata loaded from a description
+//! file) into `Sprite`/`TextureAtlas`.
+
+use std::fs;
+
+use bevy::{prelude::*, sprite::Anchor};
+use serde::Deserialize;
+
+/// One packed sprite: its grid index, pixel size, and pivot (0..1, relative
+/// to the top-left) so e.g. the sail can rotate about its luff.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AtlasSpriteDef {
+    pub name: String,
+    pub index: usize,
+    pub pixel_size: (f32, f32),
+    #[serde(default)]
+    pub pivot: (f32, f32),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AtlasDef {
+    pub texture: String,
+    pub tile_size: (f32, f32),
+    pub columns: usize,
+    pub rows: usize,
+    pub sprites: Vec<AtlasSpriteDef>,
+}
+
+/// The loaded atlas: image/layout handles plus sprite metadata, looked up by
+/// name at spawn time so future objects (buoys, wakes) can draw from it too.
+#[derive(Resource)]
+pub struct SpriteAtlas {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    entries: Vec<AtlasSpriteDef>,
+}
+
+impl SpriteAtlas {
+    pub fn entry_by_name(&self, name: &str) -> Option<&AtlasSpriteDef> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    pub fn entry(&self, index: usize) -> Option<&AtlasSpriteDef> {
+        self.entries.iter().find(|e| e.index == index)
+    }
+}
+
+pub fn sys_load_atlas(mut commands: Commands, asset_server: Res<AssetServer>, mut layouts: ResMut<Assets<TextureAtlasLayout>>) {
+    let Ok(raw) = fs::read_to_string("content/atlas/atlas.toml") else {
+        warn!("content/atlas/atlas.toml not found, sprite atlas disabled");
+        return;
+    };
+
+    let def: AtlasDef = match toml::from_str(&raw) {
+        Ok(def) => def,
+        Err(err) => {
+            warn!("failed to parse content/atlas/atlas.toml: {err}");
+            return;
+        }
+    };
+
+    let image = asset_server.load(&def.texture);
+    let layout = layouts.add(TextureAtlasLayout::from_grid(
+        UVec2::new(def.tile_size.0 as u32, def.tile_size.1 as u32),
+        def.columns as u32,
+        def.rows as u32,
+        None,
+        None,
+    ));
+
+    commands.insert_resource(SpriteAtlas { image, layout, entries: def.sprites });
+}
+
+/// References an atlas entry by name; resolved into `Sprite`/`TextureAtlas`
+/// by `sys_resolve_atlas_sprites` once the atlas has loaded. `color` tints
+/// the sprite (e.g. to tell boats apart) without needing separate art.
+/// `size_override` lets data-driven callers (e.g. a ship's sail dimensions)
+/// replace the atlas entry's `pixel_size` instead of always using it as-is.
+#[derive(Component, Reflect, Clone)]
+pub struct AtlasSprite {
+    pub name: String,
+    pub color: Color,
+    pub size_override: Option<Vec2>,
+}
+
+impl AtlasSprite {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), color: Color::WHITE, size_override: None }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size_override = Some(size);
+        self
+    }
+}
+
+pub fn sys_resolve_atlas_sprites(
+    mut commands: Commands,
+    atlas: Option<Res<SpriteAtlas>>,
+    q_sprites: Query<(Entity, &AtlasSprite), Added<AtlasSprite>>,
+) {
+    let Some(atlas) = atlas else { return };
+
+    for (entity, atlas_sprite) in &q_sprites {
+        let Some(entry) = atlas.entry_by_name(&atlas_sprite.name) else {
+            warn!("no atlas entry named {:?}", atlas_sprite.name);
+            continue;
+        };
+
+        // Anchor is relative to sprite center; pivot is relative to top-left.
+        let anchor = Anchor::Custom(Vec2::new(entry.pivot.0 - 0.5, 0.5 - entry.pivot.1));
+        let size = atlas_sprite
+            .size_override
+            .unwrap_or(Vec2::new(entry.pixel_size.0, entry.pixel_size.1));
+
+        commands.entity(entity).insert(Sprite {
+            image: atlas.image.clone(),
+            texture_atlas: Some(TextureAtlas { layout: atlas.layout.clone(), index: entry.index }),
+            custom_size: Some(size),
+            color: atlas_sprite.color,
+            anchor,
+            ..default()
+        });
+    }
+}
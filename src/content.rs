@@ -0,0 +1,182 @@
+//! Data-driven ship/sail/scenario definitions, loaded from TOML at startup
+//! (and optionally scripted with `rhai`).
+
+use std::fs;
+
+use bevy::prelude::*;
+use rhai::{Engine, Scope, AST};
+use serde::Deserialize;
+
+/// Hull + sail + keel tuning for one craft, loaded from `content/ships/*.toml`.
+#[derive(Debug, Clone, Reflect, Deserialize)]
+pub struct ShipDef {
+    pub name: String,
+    pub hull_width: f32,
+    pub hull_length: f32,
+    pub mass: f32,
+    pub keel_lateral_drag: f32,
+    pub keel_forward_drag: f32,
+    pub turn_radius: f32,
+    pub sail: SailDef,
+    #[serde(default)]
+    pub start: StartDef,
+}
+
+#[derive(Debug, Clone, Reflect, Deserialize)]
+pub struct SailDef {
+    pub width: f32,
+    pub height: f32,
+    pub cl_max: f32,
+    pub cd_0: f32,
+    pub cd_k: f32,
+}
+
+impl Default for ShipDef {
+    fn default() -> Self {
+        Self {
+            name: "Default Sloop".into(),
+            hull_width: 30.,
+            hull_length: 80.,
+            mass: 1.,
+            keel_lateral_drag: 5.0,
+            keel_forward_drag: 0.3,
+            turn_radius: 200.,
+            sail: SailDef::default(),
+            start: StartDef::default(),
+        }
+    }
+}
+
+impl Default for SailDef {
+    fn default() -> Self {
+        Self {
+            width: 75.,
+            height: 10.,
+            cl_max: 1.2,
+            cd_0: 0.05,
+            cd_k: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Reflect, Deserialize)]
+pub struct StartDef {
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+    #[serde(default)]
+    pub heading_degrees: f32,
+}
+
+/// A wind/course scenario: the starting `Wind` plus an optional `rhai` script
+/// that drives it over time (gusts, shifts, periodic veering).
+#[derive(Debug, Clone, Reflect, Deserialize)]
+pub struct ScenarioDef {
+    pub name: String,
+    pub wind: (f32, f32),
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+/// Parsed content catalog, populated at startup from TOML files under
+/// `content/`. The spawn and scenario systems use the first ship and the
+/// first scenario, once at startup; editing the catalog afterwards (e.g. in
+/// the inspector) does not respawn ships or reload the scenario.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct Catalog {
+    pub ships: Vec<ShipDef>,
+    pub scenarios: Vec<ScenarioDef>,
+}
+
+pub fn sys_load_catalog(mut commands: Commands) {
+    let mut catalog = Catalog::default();
+
+    load_toml_dir("content/ships", &mut catalog.ships);
+    load_toml_dir("content/scenarios", &mut catalog.scenarios);
+
+    commands.insert_resource(catalog);
+}
+
+fn load_toml_dir<T: for<'de> Deserialize<'de>>(dir: &str, out: &mut Vec<T>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        warn!("content directory {dir:?} not found, skipping");
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        match fs::read_to_string(&path).ok().and_then(|s| toml::from_str(&s).ok()) {
+            Some(def) => out.push(def),
+            None => warn!("failed to parse content file {path:?}"),
+        }
+    }
+}
+
+/// The currently-playing wind/course scenario's elapsed time. A plain
+/// `Resource`, unlike `ScenarioScript`, since it holds no `rhai` state.
+#[derive(Resource, Default)]
+pub struct ActiveScenario {
+    pub elapsed_secs: f32,
+}
+
+/// The compiled `rhai` script driving the active scenario's wind, if any.
+/// `Engine`/`AST` aren't `Send + Sync` without rhai's "sync" feature, so
+/// this can't be a `Resource` and is instead kept as a non-send resource,
+/// compiled once at startup so `sys_run_scenario_script` can call into it
+/// every frame without re-parsing.
+pub struct ScenarioScript(Option<(Engine, AST)>);
+
+impl ScenarioScript {
+    pub fn from_def(def: &ScenarioDef) -> Self {
+        Self(def.script.as_ref().and_then(|source| {
+            let engine = Engine::new();
+            match engine.compile(source) {
+                Ok(ast) => Some((engine, ast)),
+                Err(err) => {
+                    warn!("scenario {:?} has an invalid rhai script: {err}", def.name);
+                    None
+                }
+            }
+        }))
+    }
+
+    fn wind_at(&self, t: f32) -> Option<Vec2> {
+        let (engine, ast) = self.0.as_ref()?;
+        let mut scope = Scope::new();
+        let result: rhai::Array = engine.call_fn(&mut scope, ast, "wind", (t as f64,)).ok()?;
+        let x = result.first()?.as_float().ok()? as f32;
+        let y = result.get(1)?.as_float().ok()? as f32;
+        Some(Vec2::new(x, y))
+    }
+}
+
+pub fn sys_load_scenario(mut commands: Commands, catalog: Res<Catalog>, wind: Option<ResMut<super::Wind>>) {
+    let Some(scenario) = catalog.scenarios.first() else { return };
+
+    if let Some(mut wind) = wind {
+        wind.0 = Vec2::new(scenario.wind.0, scenario.wind.1);
+    }
+
+    commands.insert_resource(ActiveScenario::default());
+    commands.insert_non_send_resource(ScenarioScript::from_def(scenario));
+}
+
+pub fn sys_run_scenario_script(
+    time: Res<Time>,
+    scenario: Option<ResMut<ActiveScenario>>,
+    script: Option<NonSendMut<ScenarioScript>>,
+    mut wind: ResMut<super::Wind>,
+) {
+    let (Some(mut scenario), Some(script)) = (scenario, script) else { return };
+
+    scenario.elapsed_secs += time.delta_secs();
+
+    if let Some(w) = script.wind_at(scenario.elapsed_secs) {
+        wind.0 = w;
+    }
+}
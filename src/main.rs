@@ -3,6 +3,22 @@ use std::f32::consts::PI;
 use bevy::{prelude::*, math::Vec3Swizzles, window::PrimaryWindow};
 use bevy::color::palettes::basic;
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
+use bevy_rapier2d::prelude::{
+    Collider, ColliderMassProperties, ExternalForce, NoUserData, PhysicsSet, RapierPhysicsPlugin,
+    RigidBody, Velocity as RapierVelocity,
+};
+
+mod content;
+use content::{sys_load_catalog, sys_load_scenario, sys_run_scenario_script, Catalog};
+
+mod ai;
+use ai::{sys_ai_helm, AiHelm, Waypoint};
+
+mod atlas;
+use atlas::{sys_load_atlas, sys_resolve_atlas_sprites, AtlasSprite};
+
+mod tunneling;
+use tunneling::{sys_track_previous_position, sys_tunneling_guard, sys_tunneling_recovery, PreviousPosition, Tunneling};
 
 fn main() {
     let mut app = App::new();
@@ -17,6 +33,17 @@ fn main() {
         .register_type::<MousePos>()
         .register_type::<TurnRadius>()
         .register_type::<Constants>()
+        .register_type::<LateralForce>()
+        .register_type::<Keel>()
+        .register_type::<AiHelm>()
+        .register_type::<AtlasSprite>()
+        .register_type::<PreviousPosition>()
+        .register_type::<Tunneling>()
+        .register_type::<Catalog>()
+        .register_type::<content::ShipDef>()
+        .register_type::<content::SailDef>()
+        .register_type::<content::StartDef>()
+        .register_type::<content::ScenarioDef>()
     ;
 
 
@@ -28,15 +55,24 @@ fn main() {
             }),
             ..default()
         }))
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1.0))
         .add_systems(Startup, sys_setup)
-        .add_systems(Startup, sys_spawn_ship)
+        .add_systems(Startup, (sys_load_atlas, sys_load_catalog, sys_load_scenario, sys_spawn_ship, sys_spawn_ai_ship).chain())
         .add_systems(Update, sys_input)
         .add_systems(Update, sys_draw_debug_gizmos)
-        .add_systems(Update, (sys_wind_physics, sys_apply_velocity).chain())
-        .add_systems(Update, sys_circular_motion)
-        .add_systems(Update, sys_friction_physics)
+        .add_systems(Update, sys_ai_helm)
+        .add_systems(Update, sys_resolve_atlas_sprites)
+        .add_systems(Update, (sys_wind_physics, sys_keel_physics, sys_steering_physics).chain())
+        .add_systems(Update, sys_sync_velocity)
+        .add_systems(Update, sys_run_scenario_script)
         .add_systems(Update, sys_reset_xf)
         .add_systems(Update, sys_mouse_track)
+        .add_systems(
+            PostUpdate,
+            (sys_tunneling_guard, sys_tunneling_recovery, sys_track_previous_position)
+                .chain()
+                .after(PhysicsSet::Writeback),
+        )
         .insert_resource(Wind(Vec2::new(0.0, 30.0)))
         .insert_resource(DebugGizmos(Vec2::new(300.0, -200.0)))
         .insert_resource(Constants::default())
@@ -65,24 +101,68 @@ fn sys_setup(
 }
 
 
-fn sys_spawn_ship(mut cmd: Commands, mut meshes: ResMut<Assets<Mesh>>, mut colors: ResMut<Assets<ColorMaterial>>) {
+fn sys_spawn_ship(mut cmd: Commands, catalog: Res<Catalog>) {
+
+    let default_ship = content::ShipDef::default();
+    let ship_def = catalog.ships.first().unwrap_or(&default_ship);
+    let sail_def = &ship_def.sail;
+
+    let start_xf = Transform::from_xyz(ship_def.start.x, ship_def.start.y, 0.)
+        .with_rotation(Quat::from_rotation_z(ship_def.start.heading_degrees.to_radians()));
+
+    cmd.spawn(Name::new(ship_def.name.clone()))
+        .insert(Object)
+        .insert(InitialTransform(start_xf))
+        .insert(AtlasSprite::new("hull").with_color(Color::Srgba(basic::MAROON)))
+        .insert(start_xf)
+        .insert(Velocity(Vec2::new(0.0, 0.0)))
+        .insert(TurnRadius(ship_def.turn_radius))
+        .insert(LateralForce::default())
+        .insert(Keel { lateral_drag: ship_def.keel_lateral_drag, forward_drag: ship_def.keel_forward_drag })
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::cuboid(ship_def.hull_width / 2., ship_def.hull_length / 2.))
+        .insert(ColliderMassProperties::Mass(ship_def.mass))
+        .insert(ExternalForce::default())
+        .insert(RapierVelocity::zero())
+        .insert(PreviousPosition(start_xf.translation.xy()))
+        .with_children(|ship| {
+            ship.spawn(Name::new("Sail"))
+                .insert((Object, Sail { cl_max: sail_def.cl_max, cd_0: sail_def.cd_0, cd_k: sail_def.cd_k }))
+                .insert(AtlasSprite::new("sail").with_size(Vec2::new(sail_def.width, sail_def.height)))
+                .insert(Transform::from_xyz(0., 15., 1.));
+        });
+}
 
-    let sail_mesh = meshes.add(Rectangle::new(75.0, 10.0));
-    let sail_color = colors.add(Color::WHITE);
+fn sys_spawn_ai_ship(mut cmd: Commands, catalog: Res<Catalog>) {
+    let default_ship = content::ShipDef::default();
+    let ship_def = catalog.ships.first().unwrap_or(&default_ship);
+    let sail_def = &ship_def.sail;
 
-    let ship_mesh = meshes.add(Rectangle::new(30.0, 80.0));
-    let ship_color = colors.add(Color::Srgba(basic::MAROON));
+    let start_xf = Transform::from_xyz(200., 0., 0.);
 
-    cmd.spawn(Name::new("Ship"))
+    cmd.spawn(Name::new(format!("AI {}", ship_def.name)))
         .insert(Object)
-        .insert(InitialTransform(default()))
-        .insert((Mesh2d(ship_mesh), MeshMaterial2d(ship_color)))
+        .insert(InitialTransform(start_xf))
+        .insert(AtlasSprite::new("hull").with_color(Color::Srgba(basic::BLUE)))
+        .insert(start_xf)
         .insert(Velocity(Vec2::new(0.0, 0.0)))
-        .insert(TurnRadius(200.))
+        .insert(TurnRadius(f32::INFINITY))
+        .insert(LateralForce::default())
+        .insert(Keel { lateral_drag: ship_def.keel_lateral_drag, forward_drag: ship_def.keel_forward_drag })
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::cuboid(ship_def.hull_width / 2., ship_def.hull_length / 2.))
+        .insert(ColliderMassProperties::Mass(ship_def.mass))
+        .insert(ExternalForce::default())
+        .insert(RapierVelocity::zero())
+        .insert(AiHelm::new(vec![
+            Waypoint(Vec2::new(200., 600.)),
+            Waypoint(Vec2::new(-200., 900.)),
+        ]))
+        .insert(PreviousPosition(start_xf.translation.xy()))
         .with_children(|ship| {
             ship.spawn(Name::new("Sail"))
-                .insert((Object, Sail { drag_coefficient: 0.3 }))
-                .insert((Mesh2d(sail_mesh), MeshMaterial2d(sail_color)))
+                .insert((Object, Sail { cl_max: sail_def.cl_max, cd_0: sail_def.cd_0, cd_k: sail_def.cd_k }))
+                .insert(AtlasSprite::new("sail").with_size(Vec2::new(sail_def.width, sail_def.height)))
                 .insert(Transform::from_xyz(0., 15., 1.));
         });
 }
@@ -98,15 +178,14 @@ fn sys_draw_debug_gizmos(mut gizmos: Gizmos, debug_gizmos: Res<DebugGizmos>, win
     gizmos.line_2d(debug_gizmos.0, debug_gizmos.0 + SCALE_FACTOR * wind.0, basic::GREEN);
 }
 
+/// Mirrors the rigid body's `RapierVelocity` so existing gameplay/debug code
+/// keeps reading a plain `Vec2`; the physics engine now owns integration.
 #[derive(Default, Copy, Clone, Component, Reflect)]
 struct Velocity(Vec2);
 
-fn sys_apply_velocity(
-    time: Res<Time>,
-    mut q: Query<(&mut Transform, &Velocity)>,
-) {
-    for (mut transform, velocity) in q.iter_mut() {
-        transform.translation += time.delta_secs() * velocity.0.extend(0.0);
+fn sys_sync_velocity(mut q: Query<(&mut Velocity, &RapierVelocity)>) {
+    for (mut velocity, rapier_velocity) in &mut q {
+        velocity.0 = rapier_velocity.linvel;
     }
 }
 
@@ -115,9 +194,17 @@ struct Object;
 
 #[derive(Default, Copy, Clone, Component, Reflect)]
 struct Sail {
-    drag_coefficient: f32,
+    cl_max: f32,
+    cd_0: f32,
+    cd_k: f32,
 }
 
+/// Lateral (heeling) component of the sail force, resolved perpendicular to
+/// `boat_dir`. Written by `sys_wind_physics` and applied to the hull by
+/// `sys_keel_physics`.
+#[derive(Default, Copy, Clone, Component, Reflect)]
+struct LateralForce(Vec2);
+
 #[derive(Default, Copy, Clone, Component, Reflect)]
 struct TurnRadius(f32);
 
@@ -127,57 +214,97 @@ struct Wind(Vec2);
 
 
 fn sys_wind_physics(
-    time: Res<Time>,
     wind: Res<Wind>,
     constants: Res<Constants>,
-    mut q_boat: Query<(&mut Velocity, &Transform), With<Object>>,
+    mut q_boat: Query<(&mut ExternalForce, &mut LateralForce, &RapierVelocity, &Transform), With<Object>>,
     q_sail: Query<(&Sail, &GlobalTransform, &ChildOf)>,
 ) {
 
     let real_wind_v = wind.0;
-    let dt = time.delta_secs();
 
     for (sail, sail_xf, sail_parent) in q_sail.iter() {
-        let Ok((mut boat_velocity, &boat_xf)) = q_boat.get_mut(sail_parent.parent()) else { continue };
-        let boat_v = boat_velocity.0;
-        let sail_dir = sail_xf.up().xy();
+        let Ok((mut ext_force, mut lateral_force, boat_velocity, &boat_xf)) = q_boat.get_mut(sail_parent.parent()) else { continue };
+        *ext_force = ExternalForce::default();
+
+        let boat_v = boat_velocity.linvel;
+        let chord = sail_xf.up().xy();
         let boat_dir = boat_xf.up().xy();
-        let drag = sail.drag_coefficient;
 
         let apparent_wind_v = real_wind_v - boat_v;
-        let sail_f = drag * apparent_wind_v.project_onto(sail_dir);
-        let boat_f = sail_f.project_onto(boat_dir);
-        let boat_a = boat_f / constants.boat_mass;
+        if apparent_wind_v == Vec2::ZERO {
+            lateral_force.0 = Vec2::ZERO;
+            continue;
+        }
+
+        // Thin-airfoil approximation: lift peaks at alpha = 45deg, drag grows
+        // with the square of the (doubled) angle of attack.
+        let alpha = chord.angle_to(apparent_wind_v);
+        let cl = sail.cl_max * (2. * alpha).sin();
+        let cd = sail.cd_0 + sail.cd_k * (1. - (2. * alpha).cos());
+
+        let q = 0.5 * constants.air_density * apparent_wind_v.length_squared() * constants.sail_area;
+
+        let drag_dir = apparent_wind_v.normalize();
+        // Perpendicular to the apparent wind, on the low-pressure side of the sail.
+        let lift_dir = if alpha >= 0. {
+            Vec2::new(-drag_dir.y, drag_dir.x)
+        } else {
+            Vec2::new(drag_dir.y, -drag_dir.x)
+        };
+
+        let sail_f = q * (cl * lift_dir + cd * drag_dir);
+        let thrust_f = sail_f.project_onto(boat_dir);
+        lateral_force.0 = sail_f - thrust_f;
 
-        boat_velocity.0 += boat_a * dt;
+        *ext_force = ExternalForce::at_point(thrust_f, sail_xf.translation().xy(), boat_xf.translation.xy());
     }
 }
 
-fn sys_circular_motion(
-    time: Res<Time>,
-    q_object: Query<(&TurnRadius, &mut Velocity, &mut Transform)>
+/// Steers the boat by commanding a rudder torque toward the angular velocity
+/// that `TurnRadius` implies for the boat's current forward speed, replacing
+/// the old kinematic arc hack now that rotation is driven by the rigid body.
+fn sys_steering_physics(
+    constants: Res<Constants>,
+    mut q_object: Query<(&TurnRadius, &mut ExternalForce, &RapierVelocity, &Transform)>
 ) {
-    for (&TurnRadius(rad), mut v, mut xf) in q_object {
-        let dist = (v.0 * time.delta_secs()).length();
-        let dtheta = dist / rad * 2. * PI;
-        xf.rotate_z(dtheta);
-        v.0 = v.0.rotate(Vec2::from_angle(dtheta));
+    for (&TurnRadius(rad), mut ext_force, velocity, xf) in &mut q_object {
+        if rad.is_infinite() {
+            continue;
+        }
+
+        let boat_dir = xf.up().xy();
+        let v_fwd = velocity.linvel.dot(boat_dir);
+        let desired_angvel = v_fwd / rad * 2. * PI;
+        let angvel_error = desired_angvel - velocity.angvel;
+
+        ext_force.torque += constants.rudder_torque_gain * angvel_error;
     }
+}
 
+/// Anisotropic hull/keel resistance: the hull resists sideways motion far
+/// more than forward motion, and consumes the sail's residual `LateralForce`
+/// (heeling force) without fully cancelling it, producing leeway drift.
+#[derive(Default, Copy, Clone, Component, Reflect)]
+struct Keel {
+    lateral_drag: f32,
+    forward_drag: f32,
 }
 
-fn sys_friction_physics(
-    time: Res<Time>,
-    constants: Res<Constants>,
-    mut q_object: Query<&mut Velocity, With<Object>>,
+fn sys_keel_physics(
+    mut q_object: Query<(&mut ExternalForce, &RapierVelocity, &Transform, &Keel, &LateralForce), With<Object>>,
 ) {
-    for mut v in &mut q_object {
-        // |F| = c * |v|^m,
-        // F = |F| * -v/|v|,
-        // so F = -c * |v|^(m-1) * v.
-        // Use m = 2 for now
-        let f = -constants.boat_friction_coefficient * v.0.length() * v.0;
-        v.0 = v.0 + f / constants.boat_mass * time.delta_secs();
+    for (mut ext_force, velocity, xf, keel, lateral_force) in &mut q_object {
+        let boat_dir = xf.up().xy();
+        let lateral_dir = Vec2::new(-boat_dir.y, boat_dir.x);
+
+        let v_fwd = velocity.linvel.dot(boat_dir);
+        let v_lat = velocity.linvel.dot(lateral_dir);
+
+        // |F| = c * |v|^m, F = -c * |v|^(m-1) * v. Use m = 2, per axis.
+        let drag_f = -keel.forward_drag * v_fwd.abs() * v_fwd * boat_dir
+            - keel.lateral_drag * v_lat.abs() * v_lat * lateral_dir;
+
+        ext_force.force += drag_f + lateral_force.0;
     }
 }
 
@@ -188,13 +315,16 @@ struct InitialTransform(Transform);
 struct EventResetTransform;
 
 fn sys_reset_xf(mut ev_reset_xf: EventReader<EventResetTransform>,
-                mut q_transform: Query<(&mut Transform, Option<&mut Velocity>, &InitialTransform)>) {
+                mut q_transform: Query<(&mut Transform, Option<&mut Velocity>, Option<&mut RapierVelocity>, &InitialTransform)>) {
     if ev_reset_xf.read().next().is_some() {
-        for (mut xf, velocity, init_xf) in q_transform.iter_mut() {
+        for (mut xf, velocity, rapier_velocity, init_xf) in q_transform.iter_mut() {
             *xf = init_xf.0;
             if let Some(mut v) = velocity {
                 *v = Velocity(Vec2::ZERO);
             }
+            if let Some(mut rv) = rapier_velocity {
+                *rv = RapierVelocity::zero();
+            }
         }
     }
 }
@@ -206,8 +336,10 @@ struct Constants {
     sail_secs_per_rev: f32,
     wind_change_speed: f32,
     boat_turn_radius: f32,
-    boat_friction_coefficient: f32,
-    boat_mass: f32, // TODO make this part of Object
+    air_density: f32,
+    sail_area: f32,
+    rudder_torque_gain: f32,
+    ai_no_go_half_angle_degrees: f32,
 }
 
 impl Default for Constants {
@@ -216,8 +348,10 @@ impl Default for Constants {
             sail_secs_per_rev: 3.,
             wind_change_speed: 50.,
             boat_turn_radius: 400.,
-            boat_friction_coefficient: 0.01,
-            boat_mass: 1.,
+            air_density: 1.225,
+            sail_area: 0.75,
+            rudder_torque_gain: 50.,
+            ai_no_go_half_angle_degrees: 45.,
         }
     }
 }
@@ -226,8 +360,8 @@ impl Default for Constants {
 // TODO split this into separate systems for parallel processing
 fn sys_input(keys: Res<ButtonInput<KeyCode>>,
              mut evw_reset_xf: EventWriter<EventResetTransform>,
-             mut q_sail: Query<&mut Transform, With<Sail>>,
-             mut q_turn: Query<&mut TurnRadius>,
+             mut q_sail: Query<(&mut Transform, &ChildOf), With<Sail>>,
+             mut q_turn: Query<(&mut TurnRadius, Option<&AiHelm>)>,
              time: Res<Time>,
              constants: Res<Constants>,
              mut wind: ResMut<Wind>) {
@@ -252,7 +386,10 @@ fn sys_input(keys: Res<ButtonInput<KeyCode>>,
     } 
 
     if sail_needs_update {
-        for mut xf in q_sail.iter_mut() {
+        for (mut xf, sail_parent) in &mut q_sail {
+            if q_turn.get(sail_parent.parent()).is_ok_and(|(_, ai_helm)| ai_helm.is_some()) {
+                continue;
+            }
             xf.rotate_z(sail_rot);
         }
     }
@@ -286,8 +423,10 @@ fn sys_input(keys: Res<ButtonInput<KeyCode>>,
     } else if keys.pressed(KeyCode::KeyD) {
         turn_radius = -constants.boat_turn_radius;
     } 
-    for mut tr in &mut q_turn {
-        tr.0 = turn_radius;
+    for (mut tr, ai_helm) in &mut q_turn {
+        if ai_helm.is_none() {
+            tr.0 = turn_radius;
+        }
     }
 
 }
@@ -0,0 +1,95 @@
+//! Swept-collision guard against tunneling: a fast `Object` integrated with
+//! a discrete step can pass clean through a thin static collider in one
+//! frame, so each frame we cast the hull's collider along the segment from
+//! its previous position to its current one and clamp the step short if
+//! that sweep would have hit something first.
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier2d::prelude::{*, Velocity as RapierVelocity};
+
+use crate::Object;
+
+/// The entity's world position as of the end of the previous frame, used to
+/// build the swept segment.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct PreviousPosition(pub Vec2);
+
+/// Marks an entity that the tunneling guard clamped short this frame;
+/// `sys_tunneling_recovery` nudges it out along `dir` over a few frames
+/// instead of letting it sit stuck against the collider.
+#[derive(Component, Reflect, Clone, Copy)]
+pub struct Tunneling {
+    pub frames: u32,
+    pub dir: Vec2,
+}
+
+const RECOVERY_NUDGE: f32 = 0.5;
+
+pub fn sys_tunneling_guard(
+    rapier_context: ReadRapierContext,
+    mut commands: Commands,
+    mut q_object: Query<(Entity, &PreviousPosition, &mut Transform, &mut RapierVelocity, &Collider), With<Object>>,
+) {
+    let Ok(rapier_context) = rapier_context.single() else { return };
+
+    for (entity, prev, mut xf, mut velocity, collider) in &mut q_object {
+        let current = xf.translation.xy();
+        let delta = current - prev.0;
+        let dist = delta.length();
+        if dist < f32::EPSILON {
+            continue;
+        }
+        let dir = delta / dist;
+
+        let filter = QueryFilter::default().exclude_collider(entity);
+        let hit = rapier_context.cast_shape(
+            prev.0,
+            xf.rotation.to_euler(EulerRot::ZYX).0,
+            dir,
+            collider,
+            ShapeCastOptions {
+                max_time_of_impact: dist,
+                stop_at_penetration: true,
+                ..default()
+            },
+            filter,
+        );
+
+        let Some((_, hit)) = hit else { continue };
+
+        let contact_point = prev.0 + dir * hit.time_of_impact;
+        xf.translation.x = contact_point.x;
+        xf.translation.y = contact_point.y;
+
+        let normal = hit.details.map(|d| d.normal1).unwrap_or(-dir);
+        let into_normal = velocity.linvel.dot(normal);
+        if into_normal < 0. {
+            velocity.linvel -= into_normal * normal;
+        }
+
+        commands.entity(entity).insert(Tunneling { frames: 3, dir: normal });
+    }
+}
+
+pub fn sys_tunneling_recovery(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut Transform, &mut Tunneling)>,
+) {
+    for (entity, mut xf, mut tunneling) in &mut q {
+        if tunneling.frames == 0 {
+            commands.entity(entity).remove::<Tunneling>();
+            continue;
+        }
+
+        xf.translation += (tunneling.dir * RECOVERY_NUDGE * time.delta_secs()).extend(0.);
+        tunneling.frames -= 1;
+    }
+}
+
+pub fn sys_track_previous_position(mut q: Query<(&mut PreviousPosition, &Transform), With<Object>>) {
+    for (mut prev, xf) in &mut q {
+        prev.0 = xf.translation.xy();
+    }
+}
+